@@ -1,15 +1,202 @@
-use tauri::{CustomMenuItem, SystemTray, SystemTrayEvent, SystemTrayMenu};
-use std::process::{Command, Stdio};
+use tauri::{
+    AppHandle, CustomMenuItem, Manager, SystemTray, SystemTrayEvent, SystemTrayMenu,
+    SystemTrayMenuItem, SystemTraySubmenu,
+};
+use std::process::{Child, Command, Stdio};
 use std::sync::Mutex;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::net::{TcpListener, TcpStream};
+use std::io::{BufRead, BufReader, Read};
+use std::time::{Duration, Instant};
+use std::thread;
+use serde::Serialize;
 use tauri::State;
 
+/// How long we give a launched app to exit cleanly after asking it to
+/// terminate before we escalate to a forceful kill.
+const SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_secs(3);
+
+/// How often we probe a launched app's port while waiting for it to come up.
+const READINESS_POLL_INTERVAL: Duration = Duration::from_millis(250);
+/// How long we wait for a launched app to become reachable before giving up.
+const READINESS_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How many trailing log lines we keep per app for the log panel's backlog.
+const LOG_BACKLOG_CAPACITY: usize = 500;
+
+#[derive(Clone, Serialize)]
+struct AppStatusEvent {
+    app_name: String,
+    state: String,
+    port: u16,
+}
+
+#[derive(Clone, Serialize)]
+struct LogLine {
+    app_name: String,
+    stream: String,
+    line: String,
+}
+
 struct AppState {
     ports: Mutex<HashMap<String, u16>>,
+    children: Mutex<HashMap<String, Child>>,
+    logs: Mutex<HashMap<String, VecDeque<String>>>,
+    /// Apps whose `Child` was removed by a deliberate `stop_app` rather than
+    /// exiting on its own, so the readiness watcher doesn't mistake a
+    /// user-initiated stop for a launch failure.
+    stopped_intentionally: Mutex<HashSet<String>>,
+}
+
+// --- Launch script environment contract -----------------------------------
+//
+// Every launch script receives `PORT` plus the variables below, which
+// describe TerraFusion's own build rather than the host it happens to be
+// running on. Scripts can rely on these names staying stable.
+//   TERRA_PLATFORM      "windows" | "macos" | "linux"
+//   TERRA_ARCH          e.g. "x86_64", "aarch64"
+//   TERRA_FAMILY        "windows" | "unix"
+//   TERRA_TARGET_TRIPLE e.g. "x86_64-unknown-linux-gnu"
+//
+// TERRA_TARGET_TRIPLE is assembled from the build's arch and OS with a fixed
+// vendor/ABI per OS (msvc on Windows, gnu on Linux); builds for other ABIs,
+// such as musl or windows-gnu, still report the gnu/msvc form.
+
+fn terra_platform() -> &'static str {
+    if cfg!(target_os = "windows") {
+        "windows"
+    } else if cfg!(target_os = "macos") {
+        "macos"
+    } else if cfg!(target_os = "linux") {
+        "linux"
+    } else {
+        "unknown"
+    }
+}
+
+fn terra_family() -> &'static str {
+    if cfg!(target_family = "windows") {
+        "windows"
+    } else {
+        "unix"
+    }
+}
+
+fn vendor_os_for_platform(platform: &str) -> &'static str {
+    match platform {
+        "windows" => "pc-windows-msvc",
+        "macos" => "apple-darwin",
+        "linux" => "unknown-linux-gnu",
+        _ => "unknown",
+    }
+}
+
+fn terra_target_triple() -> String {
+    format!("{}-{}", std::env::consts::ARCH, vendor_os_for_platform(terra_platform()))
+}
+
+/// Which packaging layer, if any, TerraFusion itself is currently running
+/// under. Each of these rewrites `PATH`-style variables before we ever see
+/// them, so launched apps need those rewrites undone.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SandboxKind {
+    AppImage,
+    Flatpak,
+    Snap,
+    None,
+}
+
+impl SandboxKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SandboxKind::AppImage => "appimage",
+            SandboxKind::Flatpak => "flatpak",
+            SandboxKind::Snap => "snap",
+            SandboxKind::None => "none",
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+struct RuntimeInfo {
+    sandbox: String,
+}
+
+#[tauri::command]
+fn runtime_info() -> RuntimeInfo {
+    RuntimeInfo { sandbox: detect_sandbox().as_str().into() }
+}
+
+fn detect_sandbox() -> SandboxKind {
+    if std::env::var_os("APPIMAGE").is_some() || std::env::var_os("APPDIR").is_some() {
+        SandboxKind::AppImage
+    } else if std::path::Path::new("/.flatpak-info").exists() {
+        SandboxKind::Flatpak
+    } else if std::env::var_os("SNAP").is_some() {
+        SandboxKind::Snap
+    } else {
+        SandboxKind::None
+    }
+}
+
+/// The path prefix the sandbox's own runtime mounts its files under, used to
+/// recognize and drop entries it injected into `PATH`-style variables.
+fn sandbox_prefix(sandbox: SandboxKind) -> Option<std::path::PathBuf> {
+    match sandbox {
+        SandboxKind::AppImage => std::env::var_os("APPDIR").map(Into::into),
+        SandboxKind::Flatpak => Some("/app".into()),
+        SandboxKind::Snap => std::env::var_os("SNAP").map(Into::into),
+        SandboxKind::None => None,
+    }
+}
+
+/// `PATH`-style environment variables the packaging layer is known to
+/// rewrite before launching TerraFusion.
+const PATH_STYLE_VARS: &[&str] = &[
+    "PATH",
+    "LD_LIBRARY_PATH",
+    "GST_PLUGIN_PATH",
+    "GST_PLUGIN_SYSTEM_PATH",
+    "XDG_DATA_DIRS",
+    "XDG_CONFIG_DIRS",
+];
+
+/// Undo the sandbox's rewrite of `PATH`-style variables before they're
+/// inherited by a launched script: drop entries the sandbox injected under
+/// its own prefix, collapse duplicates (keeping each entry's highest-priority
+/// occurrence, i.e. shell "earliest wins" semantics), and unset anything left
+/// empty. A no-op outside a detected sandbox, so ordinary dev/CI launches
+/// inherit PATH untouched.
+fn normalize_sandbox_environment(command: &mut Command, sandbox: SandboxKind) {
+    let Some(prefix) = sandbox_prefix(sandbox) else { return };
+
+    for var in PATH_STYLE_VARS {
+        let Some(raw) = std::env::var_os(var) else { continue };
+        let cleaned = filter_and_dedup_path_entries(&raw, &prefix);
+
+        if cleaned.is_empty() {
+            command.env_remove(var);
+        } else if let Ok(joined) = std::env::join_paths(&cleaned) {
+            command.env(var, joined);
+        }
+    }
+}
+
+/// Drop entries under `prefix` and collapse duplicates, keeping each entry's
+/// first (highest-priority, "earliest wins") occurrence.
+fn filter_and_dedup_path_entries(
+    raw: &std::ffi::OsStr,
+    prefix: &std::path::Path,
+) -> Vec<std::path::PathBuf> {
+    let mut seen = HashSet::new();
+    std::env::split_paths(raw)
+        .filter(|entry| !entry.starts_with(prefix))
+        .filter(|entry| seen.insert(entry.clone()))
+        .collect()
 }
 
 #[tauri::command]
-fn launch_app(app_name: String, state: State<AppState>) -> Result<String, String> {
+fn launch_app(app_name: String, state: State<AppState>, app_handle: AppHandle) -> Result<String, String> {
     let base_path = "apps";
 
     let (command_executor, script_extension) = if cfg!(target_os = "windows") {
@@ -24,7 +211,6 @@ fn launch_app(app_name: String, state: State<AppState>) -> Result<String, String
 
     let mut ports = state.ports.lock().unwrap();
     let port = find_free_port(8000, &ports)?;
-    ports.insert(app_name.clone(), port);
 
     // Launch the script with assigned port
     let mut command = Command::new(command_executor);
@@ -33,45 +219,502 @@ fn launch_app(app_name: String, state: State<AppState>) -> Result<String, String
     } else {
         command.arg(&script_path);
     }
+    normalize_sandbox_environment(&mut command, detect_sandbox());
 
     match command
         .env("PORT", port.to_string())
-        .stdout(Stdio::null())
-        .stderr(Stdio::null())
+        .env("TERRA_PLATFORM", terra_platform())
+        .env("TERRA_ARCH", std::env::consts::ARCH)
+        .env("TERRA_FAMILY", terra_family())
+        .env("TERRA_TARGET_TRIPLE", terra_target_triple())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
         .spawn() {
-        Ok(_) => Ok(format!("{} launched on port {}", app_name, port)),
+        Ok(mut child) => {
+            if let Some(stdout) = child.stdout.take() {
+                spawn_log_reader(app_handle.clone(), app_name.clone(), "stdout", stdout);
+            }
+            if let Some(stderr) = child.stderr.take() {
+                spawn_log_reader(app_handle.clone(), app_name.clone(), "stderr", stderr);
+            }
+
+            ports.insert(app_name.clone(), port);
+            state.children.lock().unwrap().insert(app_name.clone(), child);
+            drop(ports);
+            refresh_tray_menu(&app_handle);
+            spawn_readiness_watcher(app_handle, app_name.clone(), port);
+            Ok(format!("{} launched on port {}", app_name, port))
+        }
         Err(e) => Err(format!("Failed to launch {}: {}", app_name, e)),
     }
 }
 
+#[tauri::command]
+fn get_logs(app_name: String, state: State<AppState>) -> Vec<String> {
+    state
+        .logs
+        .lock()
+        .unwrap()
+        .get(&app_name)
+        .map(|backlog| backlog.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+fn open_app(app_name: String, state: State<AppState>) -> Result<(), String> {
+    let port = *state
+        .ports
+        .lock()
+        .unwrap()
+        .get(&app_name)
+        .ok_or_else(|| format!("{} is not running", app_name))?;
+
+    open_in_default_browser(&format!("http://127.0.0.1:{}", port))
+}
+
+/// Hand a URL to the OS default browser using each platform's own dispatch
+/// mechanism; there's no portable std API for this.
+fn open_in_default_browser(url: &str) -> Result<(), String> {
+    let result = if cfg!(target_os = "windows") {
+        Command::new("cmd").args(["/C", "start", "", url]).status()
+    } else if cfg!(target_os = "macos") {
+        Command::new("open").arg(url).status()
+    } else if cfg!(target_os = "linux") {
+        open_in_linux_browser(url)
+    } else {
+        return Err("Unsupported operating system".into());
+    };
+
+    match result {
+        Ok(status) if status.success() => Ok(()),
+        Ok(status) => Err(format!("Failed to open {}: opener exited with {}", url, status)),
+        Err(e) => Err(format!("Failed to open {}: {}", url, e)),
+    }
+}
+
+/// Linux has no single standard opener; try the common desktop-environment
+/// tools in turn and fall back to an error if none of them are installed.
+fn open_in_linux_browser(url: &str) -> std::io::Result<std::process::ExitStatus> {
+    let mut last_err = std::io::Error::new(std::io::ErrorKind::NotFound, "no opener tried");
+    for opener in ["xdg-open", "gnome-open", "kde-open"] {
+        match Command::new(opener).arg(url).status() {
+            Ok(status) => return Ok(status),
+            Err(e) => last_err = e,
+        }
+    }
+    Err(last_err)
+}
+
+#[tauri::command]
+fn stop_app(app_name: String, state: State<AppState>, app_handle: AppHandle) -> Result<String, String> {
+    // Arm the flag before touching `children` so a concurrently-polling
+    // readiness watcher can never observe the child gone without it.
+    state.stopped_intentionally.lock().unwrap().insert(app_name.clone());
+
+    let mut child = match state.children.lock().unwrap().remove(&app_name) {
+        Some(child) => child,
+        None => {
+            state.stopped_intentionally.lock().unwrap().remove(&app_name);
+            return Err(format!("{} is not running", app_name));
+        }
+    };
+
+    stop_child_gracefully(&mut child);
+    state.ports.lock().unwrap().remove(&app_name);
+    // Clear it ourselves rather than trusting the watcher to: once an app is
+    // "ready" its watcher has already returned and will never consume this.
+    state.stopped_intentionally.lock().unwrap().remove(&app_name);
+    refresh_tray_menu(&app_handle);
+
+    Ok(format!("{} stopped", app_name))
+}
+
+/// Ask a child process to terminate, wait out the grace period, then kill it
+/// outright if it hasn't exited on its own.
+fn stop_child_gracefully(child: &mut Child) {
+    request_graceful_termination(child);
+
+    let deadline = Instant::now() + SHUTDOWN_GRACE_PERIOD;
+    loop {
+        match child.try_wait() {
+            Ok(Some(_)) => return,
+            Ok(None) if Instant::now() < deadline => thread::sleep(Duration::from_millis(100)),
+            _ => break,
+        }
+    }
+
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// Best-effort polite shutdown request. We shell out to the platform's own
+/// signalling tool rather than `Child::kill`, which is already a hard kill.
+fn request_graceful_termination(child: &Child) {
+    let pid = child.id().to_string();
+    if cfg!(target_os = "windows") {
+        let _ = Command::new("taskkill").args(["/PID", &pid]).output();
+    } else {
+        let _ = Command::new("kill").args(["-TERM", &pid]).output();
+    }
+}
+
+/// Read a launched app's stdout/stderr line-by-line on a background thread,
+/// keeping a bounded backlog in `AppState` and forwarding each line to the
+/// frontend as it arrives so a log panel never has to poll.
+fn spawn_log_reader<R: Read + Send + 'static>(
+    app_handle: AppHandle,
+    app_name: String,
+    stream_name: &'static str,
+    reader: R,
+) {
+    thread::spawn(move || {
+        for line in BufReader::new(reader).lines() {
+            let line = match line {
+                Ok(line) => line,
+                Err(_) => break,
+            };
+
+            {
+                let state = app_handle.state::<AppState>();
+                let mut logs = state.logs.lock().unwrap();
+                let backlog = logs.entry(app_name.clone()).or_insert_with(VecDeque::new);
+                backlog.push_back(line.clone());
+                if backlog.len() > LOG_BACKLOG_CAPACITY {
+                    backlog.pop_front();
+                }
+            }
+
+            let _ = app_handle.emit_all(
+                "app://log",
+                LogLine { app_name: app_name.clone(), stream: stream_name.into(), line },
+            );
+        }
+    });
+}
+
+/// Stop tracking an app that never became reachable: kill its child if one
+/// is still running, drop it from `ports`/`children`, and refresh the tray
+/// so a dead app can no longer be "opened" or "stopped" from it.
+fn untrack_failed_app(app_handle: &AppHandle, app_name: &str) {
+    let state = app_handle.state::<AppState>();
+    if let Some(mut child) = state.children.lock().unwrap().remove(app_name) {
+        stop_child_gracefully(&mut child);
+    }
+    state.ports.lock().unwrap().remove(app_name);
+    state.stopped_intentionally.lock().unwrap().remove(app_name);
+    refresh_tray_menu(app_handle);
+}
+
+/// Poll a freshly launched app's port on a background thread, emitting
+/// `app://status` events so the UI can flip a spinner to "ready" (or report
+/// a failure) without blocking the `launch_app` command.
+fn spawn_readiness_watcher(app_handle: AppHandle, app_name: String, port: u16) {
+    thread::spawn(move || {
+        let _ = app_handle.emit_all(
+            "app://status",
+            AppStatusEvent { app_name: app_name.clone(), state: "starting".into(), port },
+        );
+
+        let deadline = Instant::now() + READINESS_TIMEOUT;
+        loop {
+            let tracked_child_status = {
+                let state = app_handle.state::<AppState>();
+                let mut children = state.children.lock().unwrap();
+                children
+                    .get_mut(&app_name)
+                    .map(|child| matches!(child.try_wait(), Ok(Some(_))))
+            };
+
+            match tracked_child_status {
+                // The process is still running; fall through to the reachability check.
+                Some(false) => {}
+                // The process exited on its own: that's a genuine launch failure.
+                Some(true) => {
+                    let _ = app_handle.emit_all(
+                        "app://status",
+                        AppStatusEvent { app_name: app_name.clone(), state: "failed".into(), port },
+                    );
+                    untrack_failed_app(&app_handle, &app_name);
+                    return;
+                }
+                // No longer tracked: something removed it from `children` (an
+                // exited child stays there and shows up as `Some(true)` above).
+                // Only a removal that `stop_app` did not flag is a failure.
+                None => {
+                    let stopped_intentionally = app_handle
+                        .state::<AppState>()
+                        .stopped_intentionally
+                        .lock()
+                        .unwrap()
+                        .remove(&app_name);
+                    if !stopped_intentionally {
+                        let _ = app_handle.emit_all(
+                            "app://status",
+                            AppStatusEvent { app_name: app_name.clone(), state: "failed".into(), port },
+                        );
+                        untrack_failed_app(&app_handle, &app_name);
+                    }
+                    return;
+                }
+            }
+
+            if TcpStream::connect(("127.0.0.1", port)).is_ok() {
+                let _ = app_handle.emit_all(
+                    "app://status",
+                    AppStatusEvent { app_name, state: "ready".into(), port },
+                );
+                return;
+            }
+
+            if Instant::now() >= deadline {
+                let _ = app_handle.emit_all(
+                    "app://status",
+                    AppStatusEvent { app_name: app_name.clone(), state: "failed".into(), port },
+                );
+                untrack_failed_app(&app_handle, &app_name);
+                return;
+            }
+
+            thread::sleep(READINESS_POLL_INTERVAL);
+        }
+    });
+}
+
+const PORT_RANGE_END: u16 = 9000;
+
+/// Find a port in `start..PORT_RANGE_END` that isn't already tracked in
+/// `used_ports` and that we can actually bind, proving the OS agrees it's
+/// free. The bound listener is dropped immediately; we just wanted the proof.
 fn find_free_port(start: u16, used_ports: &HashMap<String, u16>) -> Result<u16, String> {
-    for port in start..9000 {
-        if !used_ports.values().any(|&v| v == port) {
+    if start >= PORT_RANGE_END {
+        return Err(format!(
+            "Port range exhausted: no ports left between {} and {}",
+            start, PORT_RANGE_END
+        ));
+    }
+
+    for port in start..PORT_RANGE_END {
+        if used_ports.values().any(|&v| v == port) {
+            continue;
+        }
+        if TcpListener::bind(("127.0.0.1", port)).is_ok() {
             return Ok(port);
         }
     }
-    Err("No free ports available".into())
+
+    Err(format!(
+        "All candidates in {}..{} are already in use",
+        start, PORT_RANGE_END
+    ))
+}
+
+/// Build the tray menu from scratch: one submenu per running app (with
+/// "Open"/"Stop" items, ids `open:<app>` / `stop:<app>`), a global "Stop All"
+/// when anything is running, and the always-present "Quit".
+fn build_tray_menu(ports: &HashMap<String, u16>) -> SystemTrayMenu {
+    let mut apps: Vec<(&String, &u16)> = ports.iter().collect();
+    apps.sort_by_key(|(app_name, _)| app_name.as_str());
+
+    let mut menu = SystemTrayMenu::new();
+    for (app_name, port) in apps {
+        let submenu = SystemTrayMenu::new()
+            .add_item(CustomMenuItem::new(format!("open:{}", app_name), "Open"))
+            .add_item(CustomMenuItem::new(format!("stop:{}", app_name), "Stop"));
+        menu = menu.add_submenu(SystemTraySubmenu::new(format!("{} ({})", app_name, port), submenu));
+    }
+
+    if !ports.is_empty() {
+        menu = menu
+            .add_native_item(SystemTrayMenuItem::Separator)
+            .add_item(CustomMenuItem::new("stop_all", "Stop All"))
+            .add_native_item(SystemTrayMenuItem::Separator);
+    }
+
+    menu.add_item(CustomMenuItem::new("quit", "Quit"))
+}
+
+/// Rebuild and install the tray menu from the current `AppState.ports`. Call
+/// this any time `ports` changes so the tray stays a live control panel.
+fn refresh_tray_menu(app_handle: &AppHandle) {
+    let state = app_handle.state::<AppState>();
+    let menu = build_tray_menu(&state.ports.lock().unwrap());
+    let _ = app_handle.tray_handle().set_menu(menu);
 }
 
 fn main() {
-    let tray_menu = SystemTrayMenu::new()
-        .add_item(CustomMenuItem::new("quit", "Quit"));
+    let tray_menu = build_tray_menu(&HashMap::new());
 
     let system_tray = SystemTray::new().with_menu(tray_menu);
 
     tauri::Builder::default()
         .manage(AppState {
             ports: Mutex::new(HashMap::new()),
+            children: Mutex::new(HashMap::new()),
+            logs: Mutex::new(HashMap::new()),
+            stopped_intentionally: Mutex::new(HashSet::new()),
         })
-        .invoke_handler(tauri::generate_handler![launch_app])
+        .invoke_handler(tauri::generate_handler![launch_app, stop_app, get_logs, runtime_info, open_app])
         .system_tray(system_tray)
         .on_system_tray_event(|app, event| {
             if let SystemTrayEvent::MenuItemClick { id, .. } = event {
-                if id.as_str() == "quit" {
-                    std::process::exit(0);
+                match id.as_str() {
+                    "quit" => {
+                        let state: State<AppState> = app.state();
+                        let mut children = state.children.lock().unwrap();
+                        for (_app_name, child) in children.iter_mut() {
+                            stop_child_gracefully(child);
+                        }
+                        std::process::exit(0);
+                    }
+                    "stop_all" => {
+                        let state: State<AppState> = app.state();
+                        let app_names: Vec<String> = state.ports.lock().unwrap().keys().cloned().collect();
+                        for app_name in app_names {
+                            let _ = stop_app(app_name, app.state(), app.clone());
+                        }
+                    }
+                    id if id.starts_with("open:") => {
+                        let app_name = id["open:".len()..].to_string();
+                        let _ = open_app(app_name, app.state());
+                    }
+                    id if id.starts_with("stop:") => {
+                        let app_name = id["stop:".len()..].to_string();
+                        let _ = stop_app(app_name, app.state(), app.clone());
+                    }
+                    _ => {}
                 }
             }
         })
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::{Path, PathBuf};
+
+    // Serializes tests that mutate process-wide environment variables.
+    static ENV_TEST_LOCK: Mutex<()> = Mutex::new(());
+
+    #[test]
+    fn find_free_port_reports_range_exhausted_past_the_end() {
+        let err = find_free_port(PORT_RANGE_END, &HashMap::new()).unwrap_err();
+        assert!(err.contains("exhausted"), "unexpected message: {}", err);
+    }
+
+    #[test]
+    fn find_free_port_skips_ports_already_tracked() {
+        let mut used = HashMap::new();
+        used.insert("other-app".to_string(), 8900u16);
+        let port = find_free_port(8900, &used).unwrap();
+        assert_ne!(port, 8900);
+    }
+
+    #[test]
+    fn find_free_port_skips_ports_the_os_refuses_to_bind() {
+        let _held = TcpListener::bind(("127.0.0.1", 8901)).expect("test port 8901 must be free");
+        let port = find_free_port(8901, &HashMap::new()).unwrap();
+        assert_ne!(port, 8901);
+    }
+
+    #[test]
+    fn find_free_port_reports_all_in_use_when_every_candidate_is_taken() {
+        let _held = TcpListener::bind(("127.0.0.1", 8999)).expect("test port 8999 must be free");
+        let err = find_free_port(8999, &HashMap::new()).unwrap_err();
+        assert!(err.contains("in use"), "unexpected message: {}", err);
+    }
+
+    #[test]
+    fn dedup_drops_entries_under_the_sandbox_prefix() {
+        let raw = std::env::join_paths(["/app/bin", "/usr/bin"]).unwrap();
+        let cleaned = filter_and_dedup_path_entries(&raw, Path::new("/app"));
+        assert_eq!(cleaned, vec![PathBuf::from("/usr/bin")]);
+    }
+
+    #[test]
+    fn dedup_keeps_the_earliest_occurrence_of_a_duplicate() {
+        let raw = std::env::join_paths(["/usr/local/bin", "/usr/bin", "/usr/local/bin"]).unwrap();
+        let cleaned = filter_and_dedup_path_entries(&raw, Path::new("/nonexistent"));
+        assert_eq!(cleaned, vec![PathBuf::from("/usr/local/bin"), PathBuf::from("/usr/bin")]);
+    }
+
+    #[test]
+    fn normalize_sandbox_environment_is_a_no_op_outside_a_sandbox() {
+        let _guard = ENV_TEST_LOCK.lock().unwrap();
+        std::env::set_var("GST_PLUGIN_PATH", "/usr/lib/gstreamer");
+        let mut command = Command::new("true");
+        normalize_sandbox_environment(&mut command, SandboxKind::None);
+        assert!(command.get_envs().next().is_none());
+        std::env::remove_var("GST_PLUGIN_PATH");
+    }
+
+    #[test]
+    fn normalize_sandbox_environment_unsets_a_variable_left_empty() {
+        let _guard = ENV_TEST_LOCK.lock().unwrap();
+        std::env::set_var("GST_PLUGIN_PATH", "/app/plugins");
+        let mut command = Command::new("true");
+        normalize_sandbox_environment(&mut command, SandboxKind::Flatpak);
+        let removed = command
+            .get_envs()
+            .any(|(key, value)| key == "GST_PLUGIN_PATH" && value.is_none());
+        assert!(removed);
+        std::env::remove_var("GST_PLUGIN_PATH");
+    }
+
+    #[test]
+    fn vendor_os_mappings_match_known_rust_target_triples() {
+        assert_eq!(vendor_os_for_platform("windows"), "pc-windows-msvc");
+        assert_eq!(vendor_os_for_platform("macos"), "apple-darwin");
+        assert_eq!(vendor_os_for_platform("linux"), "unknown-linux-gnu");
+        assert_eq!(vendor_os_for_platform("unknown"), "unknown");
+    }
+
+    #[test]
+    fn target_triple_starts_with_the_host_arch() {
+        let triple = terra_target_triple();
+        assert!(triple.starts_with(std::env::consts::ARCH));
+    }
+
+    #[test]
+    fn build_tray_menu_has_only_quit_when_nothing_is_running() {
+        let menu = build_tray_menu(&HashMap::new());
+        assert_eq!(menu.items.len(), 1);
+        match &menu.items[0] {
+            tauri::SystemTrayMenuEntry::CustomItem(item) => assert_eq!(item.id, "quit"),
+            _ => panic!("expected a single Quit item"),
+        }
+    }
+
+    #[test]
+    fn build_tray_menu_lists_a_submenu_per_app_with_stop_all() {
+        let mut ports = HashMap::new();
+        ports.insert("alpha".to_string(), 8001u16);
+        ports.insert("beta".to_string(), 8002u16);
+        let menu = build_tray_menu(&ports);
+
+        let submenu_titles: Vec<&str> = menu
+            .items
+            .iter()
+            .filter_map(|entry| match entry {
+                tauri::SystemTrayMenuEntry::Submenu(sub) => Some(sub.title.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(submenu_titles, vec!["alpha (8001)", "beta (8002)"]);
+
+        let has_stop_all = menu.items.iter().any(|entry| {
+            matches!(entry, tauri::SystemTrayMenuEntry::CustomItem(item) if item.id == "stop_all")
+        });
+        assert!(has_stop_all);
+    }
+
+    #[test]
+    fn build_tray_menu_omits_stop_all_when_nothing_is_running() {
+        let menu = build_tray_menu(&HashMap::new());
+        let has_stop_all = menu.items.iter().any(|entry| {
+            matches!(entry, tauri::SystemTrayMenuEntry::CustomItem(item) if item.id == "stop_all")
+        });
+        assert!(!has_stop_all);
+    }
+}
\ No newline at end of file